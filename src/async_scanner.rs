@@ -0,0 +1,402 @@
+// Copyright (c) Peter Sanders. All rights reserved.
+// Date: 2018-02-04
+//
+// Async counterpart to `Scanner`, gated behind the `async-scanner` feature so
+// sync-only consumers do not pull in `futures`.
+use std::str;
+
+use futures::io::{AsyncBufRead, AsyncBufReadExt};
+use regex::Regex;
+use num::Integer;
+use num::Float;
+
+/// Async/await counterpart to `Scanner` for use on `AsyncBufRead` streams,
+/// e.g. tokio/futures sockets and pipes that cannot be driven through the
+/// blocking `Read`-based `Scanner`.
+///
+/// The token-searching algorithm mirrors `Scanner::next`: skip leading
+/// `delim`s, then search for (and `.await` on) a terminating `delim`,
+/// re-polling the stream via `poll_fill_buf`/`consume` instead of blocking
+/// on it.
+///
+/// Like `Scanner::next`, this keeps accumulating data while it searches,
+/// so a delimiter larger than the underlying reader's own buffer is still
+/// found.
+pub struct AsyncScanner<R: AsyncBufRead + Unpin> {
+    stream: R, // Underlying stream object we are handling.
+    delim: Regex, // Delimiter used to specify word boundaries.
+    radix: u32, // Base in which we parse numeric types.
+}
+
+/// Implements the meta-methods of AsyncScanner that affect how the data
+/// stream is processed, e.g., delimiter, parsing radix, etc.
+impl<R: AsyncBufRead + Unpin> AsyncScanner<R> {
+    /// Sets the delimiter to be some pre-compiled regex and return it
+    /// for behavioral consistency.
+    pub fn set_delim(&mut self, delim: Regex) -> &Regex {
+        self.delim = delim;
+
+        &self.delim
+    }
+
+    /// Sets the delimiter to be a string literal. The resulting delimiting
+    /// expression is guaranteed to only interpret the literal passed in,
+    /// i.e., this method **cannot** be used to simultaneously compile and set
+    /// an arbitrary regular expression.
+    ///
+    /// We return the compiled delimiting expression.
+    pub fn set_delim_str(&mut self, delim: &str) -> &Regex {
+        // We escape any regex metacharacters, so the result is a
+        // string literal that is guaranteed to be a safe regex.
+        self.delim = Regex::new(regex::escape(delim).as_str()).unwrap();
+
+        &self.delim
+    }
+
+    /// Return the delimiter for `AsyncScanner.next()`
+    /// and methods that depend on it.
+    pub fn get_delim(&self) -> &Regex {
+        &self.delim
+    }
+
+    /// Sets the radix in which numbers are parsed. This value must be on
+    /// the closed range [2, 36], such that alphabet characters represent
+    /// values greater than 9 in bases exceeding 10.
+    ///
+    /// We return the postcondition value of the radix, which is the input
+    /// if the input is within the valid range or the precondition value
+    /// otherwise.
+    pub fn set_radix(&mut self, radix: u32) -> u32 {
+        if 1 < radix && radix <= 36 {
+            self.radix = radix;
+        }
+        self.radix
+    }
+
+    /// Retrieve the radix on which we perform numeric parsing.
+    pub fn get_radix(&self) -> u32 {
+        self.radix
+    }
+}
+
+/// Implements the methods of AsyncScanner that affect the underlying data
+/// stream.
+impl<R: AsyncBufRead + Unpin> AsyncScanner<R> {
+    /// Creates a new instance of AsyncScanner on some object implementing
+    /// `AsyncBufRead`.
+    pub fn new(stream: R) -> AsyncScanner<R> {
+        AsyncScanner {
+            stream,
+            // We can safely unwrap this regex because it is hard-coded.
+            delim: Regex::new(r"\s+").unwrap(),
+            radix: 10,
+        }
+    }
+
+    /// Returns `Some(String)` containing the next string if there is one.
+    /// Otherwise returns `None`.
+    ///
+    /// This is the `.await`-based counterpart to `Scanner::next`: it drives
+    /// the same leading-delimiter-skip + delimiter search loop, but polls
+    /// the underlying `AsyncBufRead` instead of blocking on it.
+    ///
+    /// Since `fill_buf().await` only ever hands back the bytes the stream
+    /// has ready *this* poll, and never polls again for more until we
+    /// `consume` what it gave us, we keep our own growing `acc` buffer in
+    /// place of `Scanner`'s `ElasticQueue` and always search it in full:
+    /// a delimiter spanning several polls (the common case for a real
+    /// socket or pipe trickling in a few bytes at a time) would otherwise
+    /// never be found by matching against a single poll's worth of text.
+    pub async fn next(&mut self) -> Option<String> {
+        let mut acc = String::new();
+        let offset = self.leading_delims_offset(&mut acc).await;
+        acc.drain(..offset);
+
+        let delim_idx;
+
+        loop {
+            if let Some(found) = self.delim.find(&acc) {
+                delim_idx = found.start();
+                break;
+            }
+
+            let before = acc.len();
+            match self.fetch_more(&mut acc).await {
+                Some(0) if before == 0 => {
+                    // Truly exhausted: there is no token left to return.
+                    return None;
+                }
+                Some(0) => {
+                    // EOF with no terminating delimiter: whatever we've
+                    // accumulated so far is the final token.
+                    delim_idx = before;
+                    break;
+                }
+                Some(_n) => {}
+                None => return None,
+            }
+        }
+
+        let res = acc[..delim_idx].to_string();
+
+        Some(res)
+    }
+
+    /// Read up to (but excluding) the next `\n` character.
+    /// If there are any leading `delim`s, they will be included in the
+    /// returned string.
+    ///
+    /// NOTE: unlike `next()` we do consume the trailing `\n`, if it exists.
+    pub async fn next_line(&mut self) -> Option<String> {
+        let mut res = String::new();
+
+        match self.stream.read_line(&mut res).await {
+            Ok(_size) => {
+                if let Some(end) = res.pop() {
+                    if end != '\n' {
+                        res.push(end);
+                    }
+
+                    Some(res)
+                } else {
+                    None
+                }
+            }
+            Err(_e) => None,
+        }
+    }
+
+    /// Attempts to retrieve the next integer of the specified (or inferred)
+    /// type. Even if this fails, we still consume `next`.
+    ///
+    /// The default radix for this parsing is 10; see `AsyncScanner::set_radix`.
+    pub async fn next_int<T: Integer>(&mut self) -> Option<T> {
+        if let Some(mut input) = self.next().await {
+            // Strip commas. Numbers with commas are considered valid
+            // but Rust does not recognize them in its default behavior.
+            while let Some(comma_idx) = input.rfind(',') {
+                input.remove(comma_idx);
+            }
+
+            match <T>::from_str_radix(input.as_str(), self.radix) {
+                Ok(res) => Some(res),
+                Err(_e) => None,
+            }
+        } else {
+            None
+        }
+    }
+
+    /// Attempts to retrieve the next floating-point number of the specified
+    /// (or inferred) type. Even if this fails, we still consume `next`.
+    ///
+    /// Note that this method is based on `AsyncScanner.next()`, so the
+    /// delimiter is still the same.
+    pub async fn next_float<T: Float>(&mut self) -> Option<T> {
+        if let Some(mut input) = self.next().await {
+            while let Some(comma_idx) = input.rfind(',') {
+                input.remove(comma_idx);
+            }
+
+            match <T>::from_str_radix(input.as_str(), self.radix) {
+                Ok(res) => Some(res),
+                Err(_e) => None,
+            }
+        } else {
+            None
+        }
+    }
+}
+
+/// Private helper functions for AsyncScanner
+impl<R: AsyncBufRead + Unpin> AsyncScanner<R> {
+    /// Polls the stream once and, if it has bytes ready, appends them to
+    /// `acc` and immediately `consume`s them from the stream so the next
+    /// `fill_buf().await` is forced to poll for genuinely new data rather
+    /// than handing back the same already-seen bytes.
+    ///
+    /// Returns `Some(n)` with the number of bytes appended (`Some(0)`
+    /// meaning the stream has reached EOF), or `None` on an I/O error or
+    /// invalid utf-8.
+    async fn fetch_more(&mut self, acc: &mut String) -> Option<usize> {
+        let buf = self.stream.fill_buf().await.ok()?;
+        let n = buf.len();
+
+        if n > 0 {
+            let text = str::from_utf8(buf).ok()?;
+            acc.push_str(text);
+            self.stream.consume_unpin(n);
+        }
+
+        Some(n)
+    }
+
+    /// When we read `AsyncScanner.next()`, we must first skip over any
+    /// strings in the delimiting language before we begin reading the
+    /// target text. Mirrors `Scanner::leading_delims_offset`: `acc` plays
+    /// the role of `Scanner`'s persistent `ElasticQueue` lookahead buffer,
+    /// growing as `fetch_more` polls in new data, so every match is made
+    /// against everything fetched so far rather than just the latest poll.
+    ///
+    /// A delimiter match that reaches exactly the end of what we have
+    /// fetched so far is not committed immediately: a greedy operator in
+    /// `delim` (e.g. `a[ab]*b`) could extend it further once more data
+    /// arrives, so we fetch more and re-match before deciding it is final.
+    async fn leading_delims_offset(&mut self, acc: &mut String) -> usize {
+        let mut res: usize = 0;
+
+        if acc.is_empty() && self.fetch_more(acc).await.is_none() {
+            return res;
+        }
+
+        loop {
+            let avail = acc.len();
+            let found = self.delim.find(&acc[res..]).map(|m| (m.start(), m.end()));
+
+            match found {
+                Some((start, _end)) if start > 0 => return res,
+                Some((_start, end)) if res + end < avail => {
+                    res += end;
+                    continue;
+                }
+                _ => {}
+            }
+
+            match self.fetch_more(acc).await {
+                Some(0) => {
+                    // EOF: a pending match at the boundary is now final.
+                    if let Some((0, end)) = found {
+                        res += end;
+                    }
+                    return res;
+                }
+                Some(_n) => {}
+                None => return res,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::executor::block_on;
+    use futures::io::{AsyncRead, BufReader};
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    /// An `AsyncRead` that hands back at most one byte per `poll_read`,
+    /// standing in for a socket/pipe that does not deliver a whole token
+    /// in a single read. `AsyncScanner::next()`'s EOF/stagnation handling
+    /// must still reassemble the full token across many such polls.
+    struct OneByteAtATime<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl<'a> AsyncRead for OneByteAtATime<'a> {
+        fn poll_read(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &mut [u8],
+        ) -> Poll<std::io::Result<usize>> {
+            let this = self.get_mut();
+            if this.pos >= this.data.len() || buf.is_empty() {
+                return Poll::Ready(Ok(0));
+            }
+
+            buf[0] = this.data[this.pos];
+            this.pos += 1;
+
+            Poll::Ready(Ok(1))
+        }
+    }
+
+    /// This test will fail if `next()` ever mistakes "re-polling returned
+    /// the same buffered bytes" for EOF, which would truncate the token
+    /// to whatever happened to be buffered on the first poll.
+    #[test]
+    fn next_reassembles_a_token_delivered_one_byte_at_a_time() {
+        let reader = BufReader::new(OneByteAtATime {
+            data: b"hello world",
+            pos: 0,
+        });
+        let mut scanner = AsyncScanner::new(reader);
+
+        block_on(async {
+            assert_eq!(scanner.next().await, Some(String::from("hello")));
+            assert_eq!(scanner.next().await, Some(String::from("world")));
+            assert_eq!(scanner.next().await, None);
+        });
+    }
+
+    /// Same as above, but for leading delimiters that straddle several
+    /// one-byte polls rather than a single token.
+    #[test]
+    fn next_skips_leading_delims_delivered_one_byte_at_a_time() {
+        let reader = BufReader::new(OneByteAtATime {
+            data: b"   word",
+            pos: 0,
+        });
+        let mut scanner = AsyncScanner::new(reader);
+
+        block_on(async {
+            assert_eq!(scanner.next().await, Some(String::from("word")));
+        });
+    }
+
+    /// This test will fail if `next()` only matches `delim` against a
+    /// single `fill_buf().await` poll's worth of text instead of against
+    /// everything fetched so far: with one byte arriving per poll, a
+    /// two-byte delimiter like `::` never fits inside any one poll.
+    #[test]
+    fn next_finds_a_delimiter_spanning_several_one_byte_polls() {
+        let reader = BufReader::new(OneByteAtATime {
+            data: b"key::value",
+            pos: 0,
+        });
+        let mut scanner = AsyncScanner::new(reader);
+        scanner.set_delim_str("::");
+
+        block_on(async {
+            assert_eq!(scanner.next().await, Some(String::from("key")));
+            assert_eq!(scanner.next().await, Some(String::from("value")));
+            assert_eq!(scanner.next().await, None);
+        });
+    }
+
+    /// Mirrors `buffer_ends_within_start_delim` in `tests.rs`: a leading
+    /// delimiter that is still being assembled one poll at a time must not
+    /// be mistaken for the token itself.
+    #[test]
+    fn leading_delim_reassembled_across_one_byte_polls() {
+        let reader = BufReader::new(OneByteAtATime {
+            data: b"aaaabfoo",
+            pos: 0,
+        });
+        let mut scanner = AsyncScanner::new(reader);
+        scanner.set_delim(Regex::new(r"a+b").unwrap());
+
+        block_on(async {
+            assert_eq!(scanner.next().await, Some(String::from("foo")));
+        });
+    }
+
+    /// Mirrors `buffer_boundary_preserves_greed` in `tests.rs`: a match
+    /// that reaches exactly the end of what's been fetched so far must not
+    /// be committed early, since a greedy operator could extend it once
+    /// the next poll arrives.
+    #[test]
+    fn leading_delim_match_at_poll_boundary_preserves_greed() {
+        let reader = BufReader::new(OneByteAtATime {
+            data: b"aaabbfoo",
+            pos: 0,
+        });
+        let mut scanner = AsyncScanner::new(reader);
+        scanner.set_delim(Regex::new(r"a[ab]*b").unwrap());
+
+        block_on(async {
+            assert_eq!(scanner.next().await, Some(String::from("foo")));
+        });
+    }
+}
@@ -1,8 +1,21 @@
 // Copyright (c) Peter Sanders. All rights reserved.
 // Date: 2018-02-03
 
+// Routed through `alloc` rather than `std` under the `no_std` feature, so
+// this module (and therefore `Scanner`'s lookahead buffer) compiles on a
+// `no_std + alloc` target; see the `no_std` notes in `lib.rs`.
+#[cfg(not(feature = "no_std"))]
+use std::cmp;
+#[cfg(not(feature = "no_std"))]
 use std::vec::Vec;
 
+#[cfg(feature = "no_std")]
+extern crate alloc;
+#[cfg(feature = "no_std")]
+use core::cmp;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+
 #[cfg(test)]
 mod tests;
 
@@ -17,7 +30,7 @@ const DEFAULT_BUF_SIZE: usize = 1024*64;  // The default used by `BufReader`.
 /// - It shall have the Queue property that input corresponds to output.
 ///
 /// ## Elasticity
-/// 
+///
 /// ### Fixed Minimum Capacity
 ///
 /// The buffer cannot be allowed to shrink arbitrarily small or else we have
@@ -71,11 +84,7 @@ impl<T> ElasticQueue<T> {
     /// For primitive or reference types in applications with loose memory
     /// constraints, one should usually choose this function.
     pub fn new() -> ElasticQueue<T> {
-        Self {
-            buf: Vec::with_capacity(DEFAULT_BUF_SIZE),
-            cap: DEFAULT_BUF_SIZE,
-            pos: 0
-        }
+        Self::with_capacity(DEFAULT_BUF_SIZE)
     }
 
     /// This instantiator allows the user to specify the capacity. Its
@@ -89,14 +98,110 @@ impl<T> ElasticQueue<T> {
         Self {
             buf: Vec::with_capacity(capacity),
             cap: capacity,
-            pos: 0
+            read_pos: 0,
+            write_pos: 0,
         }
     }
 }
 
+/// # Inspecting the queue
 impl<T> ElasticQueue<T> {
+    /// The number of elements currently buffered (enqueued but not yet
+    /// consumed).
+    pub fn len(&self) -> usize {
+        self.write_pos - self.read_pos
+    }
+
+    /// Whether there are any buffered elements left to consume.
+    pub fn is_empty(&self) -> bool {
+        self.read_pos == self.write_pos
+    }
+
+    /// The persistent capacity of the queue, i.e., the size it contracts
+    /// back down to once a `stretch`ed region has been `consume`d.
+    pub fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    /// Returns the filled region of the buffer as a single contiguous
+    /// slice, exactly as `BufRead::fill_buf` requires. This is an O(1)
+    /// `slice` operation; no data is copied or shifted.
+    pub fn as_slice(&self) -> &[T] {
+        &self.buf[self.read_pos..self.write_pos]
+    }
+}
+
+/// # Inserting and removing elements
+impl<T> ElasticQueue<T> {
+    /// Appends a single element to the back of the queue.
+    ///
+    /// This never refuses to write: if the caller needs lookahead beyond
+    /// `cap`, it should call `stretch` first so that the extra memory is
+    /// reserved up front rather than being grown one element at a time.
+    pub fn enqueue(&mut self, item: T) {
+        if self.write_pos < self.buf.len() {
+            self.buf[self.write_pos] = item;
+        } else {
+            self.buf.push(item);
+        }
+        self.write_pos += 1;
+    }
 
-    pub fn enqueue(item: T) {
-        
+    /// Temporarily reserves room for at least `additional` more elements
+    /// beyond those currently buffered, even if that takes the backing
+    /// storage past `cap`, so that a lookahead search may accumulate more
+    /// data than the queue would normally hold.
+    ///
+    /// The extra memory is given back automatically the next time
+    /// `consume` drains the queue back down to `cap` or fewer live
+    /// elements.
+    pub fn stretch(&mut self, additional: usize) {
+        self.buf.reserve(additional);
+    }
+
+    /// Removes the first `amt` elements from the front of the queue.
+    ///
+    /// This is the queue's one destructive operation: it slides any
+    /// remaining live elements down to the front of the backing `Vec` so
+    /// that `write_pos` cannot grow without bound, and, if the buffer had
+    /// been `stretch`ed past `cap`, shrinks the backing storage back down
+    /// to `cap`.
+    pub fn consume(&mut self, amt: usize) {
+        self.read_pos = cmp::min(self.read_pos + amt, self.write_pos);
+
+        if self.read_pos == self.write_pos {
+            // Fully drained: reclaim the whole buffer in one shot.
+            self.buf.clear();
+            self.read_pos = 0;
+            self.write_pos = 0;
+            if self.buf.capacity() > self.cap {
+                self.buf.shrink_to(self.cap);
+            }
+            return;
+        }
+
+        if self.read_pos == 0 {
+            return;
+        }
+
+        self.buf.drain(..self.read_pos);
+        self.write_pos -= self.read_pos;
+        self.read_pos = 0;
+
+        if self.buf.capacity() > self.cap {
+            self.buf.shrink_to(self.cap);
+        }
+    }
+}
+
+/// # Bulk insertion for `Copy` element types
+///
+/// This is the common case for `Scanner`, which buffers `u8`.
+impl<T: Copy> ElasticQueue<T> {
+    /// Appends every element of `items` to the back of the queue, in order.
+    pub fn enqueue_slice(&mut self, items: &[T]) {
+        for item in items {
+            self.enqueue(*item);
+        }
     }
 }
@@ -0,0 +1,88 @@
+// Copyright (c) Peter Sanders. All rights reserved.
+// Date: 2018-02-03
+//
+// Unit tests for ElasticQueue.
+use super::*;
+
+#[test]
+fn new_queue_is_empty() {
+    let queue: ElasticQueue<u8> = ElasticQueue::new();
+
+    assert_eq!(queue.len(), 0);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn enqueue_appends_in_order() {
+    let mut queue: ElasticQueue<u8> = ElasticQueue::with_capacity(4);
+    queue.enqueue(1);
+    queue.enqueue(2);
+    queue.enqueue(3);
+
+    assert_eq!(queue.as_slice(), &[1, 2, 3]);
+}
+
+#[test]
+fn enqueue_slice_appends_in_order() {
+    let mut queue: ElasticQueue<u8> = ElasticQueue::with_capacity(8);
+    queue.enqueue_slice(&[1, 2, 3, 4]);
+
+    assert_eq!(queue.as_slice(), &[1, 2, 3, 4]);
+}
+
+#[test]
+fn consume_advances_read_position() {
+    let mut queue: ElasticQueue<u8> = ElasticQueue::with_capacity(8);
+    queue.enqueue_slice(&[1, 2, 3, 4]);
+    queue.consume(2);
+
+    assert_eq!(queue.as_slice(), &[3, 4]);
+    assert_eq!(queue.len(), 2);
+}
+
+#[test]
+fn consume_past_end_does_not_panic() {
+    let mut queue: ElasticQueue<u8> = ElasticQueue::with_capacity(8);
+    queue.enqueue_slice(&[1, 2]);
+    queue.consume(100);
+
+    assert!(queue.is_empty());
+}
+
+/// After a full drain, the queue should be able to accept new elements at
+/// the front of the buffer again rather than growing without bound.
+#[test]
+fn queue_is_reusable_after_full_drain() {
+    let mut queue: ElasticQueue<u8> = ElasticQueue::with_capacity(4);
+    queue.enqueue_slice(&[1, 2, 3, 4]);
+    queue.consume(4);
+    queue.enqueue_slice(&[5, 6]);
+
+    assert_eq!(queue.as_slice(), &[5, 6]);
+}
+
+/// This test will fail if `stretch` followed by a draining `consume` does
+/// not return the backing storage to its persistent capacity.
+#[test]
+fn stretch_contracts_back_to_capacity_on_consume() {
+    let mut queue: ElasticQueue<u8> = ElasticQueue::with_capacity(4);
+    queue.enqueue_slice(&[1, 2, 3, 4]);
+    queue.stretch(60);
+    queue.enqueue_slice(&[5, 6, 7, 8]);
+    assert_eq!(queue.as_slice(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+    queue.consume(8);
+
+    assert!(queue.buf.capacity() <= 4);
+}
+
+#[test]
+fn partial_consume_preserves_unread_tail_after_stretch() {
+    let mut queue: ElasticQueue<u8> = ElasticQueue::with_capacity(4);
+    queue.enqueue_slice(&[1, 2, 3, 4]);
+    queue.stretch(4);
+    queue.enqueue_slice(&[5, 6, 7, 8]);
+    queue.consume(6);
+
+    assert_eq!(queue.as_slice(), &[7, 8]);
+}
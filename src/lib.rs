@@ -1,32 +1,171 @@
 // Copyright (c) Peter Sanders. All rights reserved.
 // Date: 2018-02-04
-extern crate buf_redux;
+#![cfg_attr(feature = "no_std", no_std)]
 extern crate num;
 extern crate regex;
+#[cfg(feature = "async-scanner")]
+extern crate futures;
 
-use std::io::Read;
-use std::io::BufRead;
+// `no_std` support: the I/O bound (`Read`/`BufRead`/`Seek`/`SeekFrom`/`io`)
+// is re-exported from either `std::io` or our own `no_std_io`, and
+// `String`/`Vec` come from either `std` or `alloc`, depending on the
+// `no_std` feature.
+//
+// This only gets us as far as "no_std + alloc", not "no_std with no
+// allocator at all": `ElasticQueue`'s lookahead buffer is still a growable
+// `Vec` either way. `next_into`/`next_until_into` write each frame into a
+// caller-provided `&mut [u8]` instead of returning an owned `Vec`/`String`,
+// but they still fill that same allocating lookahead buffer, so they too
+// need an allocator (see `next_into` below).
+//
+// `regex`, our delimiter engine for `next()`/`next_line()`, is used in
+// either mode: `Scanner` always holds a `Regex` delimiter, so this feature
+// builds only where `regex` itself builds without `std`. The
+// byte-delimiter half added for binary scanning (`next_bytes`/`next_until`/
+// `next_into`) never calls into `regex`.
+//
+// We do not use `core_io` for this in place of `no_std_io` below: its
+// build script only recognizes a hardcoded table of pre-2019 nightly
+// commit hashes and panics on any current toolchain; see `no_std_io`'s
+// doc comment.
+#[cfg(not(feature = "no_std"))]
+use std::cmp;
+#[cfg(not(feature = "no_std"))]
+pub use std::io;
+#[cfg(not(feature = "no_std"))]
+pub use std::io::Read;
+#[cfg(not(feature = "no_std"))]
+pub use std::io::BufRead;
+#[cfg(not(feature = "no_std"))]
+pub use std::io::Seek;
+#[cfg(not(feature = "no_std"))]
+pub use std::io::SeekFrom;
+#[cfg(not(feature = "no_std"))]
+use std::marker::PhantomData;
+#[cfg(not(feature = "no_std"))]
 use std::marker::Sized;
+#[cfg(not(feature = "no_std"))]
 use std::str;
 
-use buf_redux::BufReader;
+#[cfg(feature = "no_std")]
+extern crate alloc;
+#[cfg(feature = "no_std")]
+use core::cmp;
+#[cfg(feature = "no_std")]
+pub use no_std_io as io;
+#[cfg(feature = "no_std")]
+pub use no_std_io::Read;
+#[cfg(feature = "no_std")]
+pub use no_std_io::BufRead;
+#[cfg(feature = "no_std")]
+pub use no_std_io::Seek;
+#[cfg(feature = "no_std")]
+pub use no_std_io::SeekFrom;
+#[cfg(feature = "no_std")]
+use core::marker::PhantomData;
+#[cfg(feature = "no_std")]
+use core::marker::Sized;
+#[cfg(feature = "no_std")]
+use core::str;
+#[cfg(feature = "no_std")]
+use alloc::string::String;
+#[cfg(feature = "no_std")]
+use alloc::vec;
+#[cfg(feature = "no_std")]
+use alloc::vec::Vec;
+
 use regex::Regex; // For regex "delim"
 use num::Integer;
 use num::Float;
 
+use elastic_queue::ElasticQueue;
+
+mod elastic_queue;
+
+#[cfg(feature = "no_std")]
+mod no_std_io;
+
+#[cfg(feature = "async-scanner")]
+mod async_scanner;
+#[cfg(feature = "async-scanner")]
+pub use async_scanner::AsyncScanner;
+
 #[cfg(test)]
 mod tests;
 
 const DEFAULT_BUF_SIZE: usize = 1024 * 8;
 
+// Adaptive growth parameters for the lookahead loop in `Scanner::next`: we
+// start small so tokenizing tiny tokens does not force a large reservation,
+// and double on each miss, up to `MAX_STRETCH`, so tokenizing huge tokens
+// still converges quickly.
+const MIN_STRETCH: usize = 32;
+const MAX_STRETCH: usize = 1024 * 64;
+
+// The chunk size used to walk backward over a `Seek` stream in `next_back`
+// and `prev_line`. Unlike the forward lookahead, there is no adaptive
+// growth here: each miss simply pulls one more chunk's worth of history.
+const BACK_CHUNK_SIZE: usize = 1024 * 8;
+
 /// Rust implementation of java.util.Scanner
 pub struct Scanner<R: Read + Sized> {
-    stream: BufReader<R>, // Underlying stream object we are handling.
+    reader: R, // Underlying stream object we are handling.
+    buf: ElasticQueue<u8>, // Our own `BufRead`-style lookahead buffer.
     delim: Regex,  // Delimiter used to specify word boundaries.
+    byte_delim: Vec<u8>, // Delimiter used by `next_bytes`, as raw bytes.
     radix: u32,  // Base in which we parse numeric types.
 
-    // See `impl BufRead for Scanner` block for details.
-    // TODO(hxtk): Implement BufRead. Pending Issue #5.
+    // Lazily-initialized state for reverse scanning (`next_back`,
+    // `prev_line`). Only ever touched by `impl<R: Read + Seek>`.
+    back: Option<RevCursor>,
+}
+
+// Backing state for reverse scanning: `buf` holds every byte read so far
+// from the tail of the stream, starting at stream offset `pos`. We grow it
+// by seeking to `pos - chunk` and prepending, rather than re-reading what
+// we already have.
+struct RevCursor {
+    pos: u64,
+    buf: Vec<u8>,
+}
+
+// Skips any leading UTF-8 continuation bytes (`10xxxxxx`) in `buf`, i.e.
+// the tail of a multi-byte character whose leading byte has not been read
+// yet. A continuation sequence is at most 3 bytes long, so this always
+// terminates well within `buf`'s bounds.
+fn first_char_boundary(buf: &[u8]) -> usize {
+    let mut i = 0;
+    while i < buf.len() && i < 4 && (buf[i] & 0b1100_0000) == 0b1000_0000 {
+        i += 1;
+    }
+    i
+}
+
+// Finds the first occurrence of `needle` in `haystack`, or `None`. This is
+// a streaming-friendly substring search: a scan for `needle`'s first byte
+// (playing the role a `memchr` would) followed by a slice compare at each
+// candidate, so it is cheap to re-run against a buffer that is only
+// growing, one `stretch` at a time, between calls.
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || haystack.len() < needle.len() {
+        return None;
+    }
+
+    let first = needle[0];
+    let mut start = 0;
+
+    while let Some(rel) = haystack[start..].iter().position(|&b| b == first) {
+        let idx = start + rel;
+        if idx + needle.len() > haystack.len() {
+            return None;
+        }
+        if &haystack[idx..idx + needle.len()] == needle {
+            return Some(idx);
+        }
+        start = idx + 1;
+    }
+
+    None
 }
 
 /// Implements the meta-methods of Scanner that affect how the data stream
@@ -60,6 +199,22 @@ impl<R: Read + Sized> Scanner<R> {
         &self.delim
     }
 
+    /// Sets the byte-sequence delimiter used by `Scanner.next_bytes()`.
+    ///
+    /// Unlike `set_delim`/`set_delim_str`, this is a literal byte sequence
+    /// matched directly against the raw buffer, with no UTF-8 validation,
+    /// so it also works on streams that are not valid text.
+    pub fn set_delim_bytes(&mut self, delim: &[u8]) -> &[u8] {
+        self.byte_delim = delim.to_vec();
+
+        &self.byte_delim
+    }
+
+    /// Return the delimiter for `Scanner.next_bytes()`.
+    pub fn get_delim_bytes(&self) -> &[u8] {
+        &self.byte_delim
+    }
+
     /// Sets the radix in which numbers are parsed. This value must be on
     /// the closed range [2, 36], such that alphabet characters represent
     /// values greater than 9 in bases exceeding 10.
@@ -78,6 +233,13 @@ impl<R: Read + Sized> Scanner<R> {
     pub fn get_radix(&self) -> u32 {
         self.radix
     }
+
+    /// Returns the persistent capacity of the lookahead buffer, i.e., the
+    /// size it contracts back down to once a `stretch`ed region has been
+    /// consumed. See `Scanner::with_capacity` to set this at construction.
+    pub fn buffer_capacity(&self) -> usize {
+        self.buf.capacity()
+    }
 }
 
 /// Implements the methods of Scanner that affect the underlying data stream
@@ -85,94 +247,295 @@ impl<R: Read + Sized> Scanner<R> {
     /// Creates a new instance of Scanner on some object implementing `Read`
     pub fn new(stream: R) -> Scanner<R> {
         Scanner {
-            stream: BufReader::new(stream),
+            reader: stream,
+            buf: ElasticQueue::new(),
             // We can safely unwrap this regex because it is hard-coded.
             delim: Regex::new(r"\s+").unwrap(),
+            byte_delim: vec![b'\n'],
             radix: 10,
+            back: None,
         }
     }
 
-    /// Creates a new instance of Scanner using a BufReader with a specified
-    /// buffer size.
+    /// Creates a new instance of Scanner using a lookahead buffer with a
+    /// specified capacity.
     ///
     /// This instantiator allows the user to specify the capacity of the buffer.
     /// Its primary use-case is unit testing this module, i.e., it would be
     /// cumbersome to write 64KB test strings so one might specify a
     /// capacity of only a few bytes in order to test what happens at the
+    /// boundary.
+    ///
+    /// The capacity is the size the buffer contracts back down to after a
+    /// `stretch`; it does not limit how many bytes a single read pulls from
+    /// `stream`.
     pub fn with_capacity(size: usize, stream: R) -> Scanner<R> {
         Scanner {
-            stream: BufReader::with_capacity(size, stream),
+            reader: stream,
+            buf: ElasticQueue::with_capacity(size),
             // We can safely unwrap this regex because it is hard-coded.
             delim: Regex::new(r"\s+").unwrap(),
+            byte_delim: vec![b'\n'],
             radix: 10,
+            back: None,
+        }
+    }
+
+    /// Returns a double-ended iterator over the remaining tokens.
+    ///
+    /// The forward direction simply calls `next()`. When `R` also
+    /// implements `Seek`, the iterator is `DoubleEndedIterator`, and
+    /// calling `.next_back()` on it calls `Scanner.next_back()`, pulling
+    /// tokens from the tail of the stream instead.
+    pub fn tokens(&mut self) -> Tokens<'_, R> {
+        Tokens { scanner: self }
+    }
+
+    /// Returns an iterator that yields `next_int::<T>()` until it first
+    /// returns `None`, e.g. `scanner.ints::<u64>().sum()`.
+    ///
+    /// Like `next_int`, a token that fails to parse ends the iterator; it
+    /// does not skip over non-numeric tokens to find the next valid one.
+    pub fn ints<T: Integer>(&mut self) -> Ints<'_, R, T> {
+        Ints {
+            scanner: self,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that yields `next_float::<T>()` until it first
+    /// returns `None`, the floating-point counterpart to `ints`.
+    pub fn floats<T: Float>(&mut self) -> Floats<'_, R, T> {
+        Floats {
+            scanner: self,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns `true` if there is another token available from `next()`,
+    /// without consuming it.
+    pub fn has_next(&mut self) -> bool {
+        self.peek_next().is_some()
+    }
+
+    /// Returns `true` if there is another token available from `next()`
+    /// and it parses as a `T` under the current radix, without consuming
+    /// it either way.
+    pub fn has_next_int<T: Integer>(&mut self) -> bool {
+        match self.peek_next() {
+            Some(mut input) => {
+                while let Some(comma_idx) = input.rfind(',') {
+                    input.remove(comma_idx);
+                }
+
+                <T>::from_str_radix(input.as_str(), self.radix).is_ok()
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if there is another line available from
+    /// `next_line()`, without consuming it.
+    pub fn has_next_line(&mut self) -> bool {
+        match self.fill_buf() {
+            Ok(buf) => !buf.is_empty(),
+            Err(_e) => false,
         }
     }
 
     /// Returns `Some(String)` containing the next string if there is one.
     /// Otherwise returns `None`.
     ///
-    /// We first consume all leading `delim`s that fit within the buffer of the
-    /// underlying `BufRead`, then attempt to read everything until
-    /// (but excluding) the next `delim` which is entirely contained within a
-    /// single buffer. We guarantee this will behave as expected if the longest
-    /// single precendent delimiter is no larger than the size of the buffer.
-    ///
-    /// Otherwise it will fail.
+    /// We first consume all leading `delim`s that fit within the buffer,
+    /// then attempt to read everything until (but excluding) the next
+    /// `delim`. Unlike earlier versions of this method, we no longer give up
+    /// when a terminating `delim` does not fit in a single buffer: we grow
+    /// the lookahead buffer adaptively (doubling, up to a cap) and keep
+    /// searching until we either find it or exhaust the stream.
     pub fn next(&mut self) -> Option<String> {
-        let offset = {
-            self.leading_delims_offset()
-        };
-        self.stream.consume(offset);
+        let offset = self.leading_delims_offset();
+        self.buf.consume(offset);
 
         let delim_idx;
-        let mut res = String::new();
-        let mut last_length = 0;
+        let mut stretch = MIN_STRETCH;
 
         loop {
-                
-            let delta = {
-                if let Ok(_size) = self.stream.read_into_buf() {
-                    let buf = self.stream.get_buf();
-                    // If the buffer is not a valid utf-8 string, we exit the
-                    // method with `None` result.
-                    if str::from_utf8(buf).is_err() {
-                        return None;
-                    }
-                    
-                    // The check above guarantees `unwrap` will succeed.
-                    res = String::from(str::from_utf8(buf).unwrap());
+            let found = {
+                let text = str::from_utf8(self.buf.as_slice()).ok()?;
+                self.delim.find(text).map(|m| m.start())
+            };
 
-                    let old_len = last_length;
-                    last_length = buf.len();
+            if let Some(idx) = found {
+                delim_idx = idx;
+                break;
+            }
 
-                    buf.len() - old_len
-                } else {
-                    0
+            let before = self.buf.len();
+            self.buf.stretch(stretch);
+            match self.pull(stretch) {
+                Ok(0) if before == 0 => {
+                    // Truly exhausted: there is no token left to return.
+                    return None;
                 }
-            };
-            
-            if delta == 0 {
-                delim_idx = res.len();
-                break;
+                Ok(0) => {
+                    // The stream is exhausted: whatever is left in the
+                    // buffer is the final token.
+                    delim_idx = before;
+                    break;
+                }
+                Ok(_n) => {}
+                Err(_e) => return None,
             }
 
-            // If a delimiter is found within the result string, we stop reading
-            // and mark the location. Everything up to here should be consumed.
-            if let Some(found) = self.delim.find(res.as_str()) {
-                delim_idx = found.start();
+            stretch = cmp::min(stretch * 2, MAX_STRETCH);
+        }
+
+        let res = String::from(str::from_utf8(&self.buf.as_slice()[..delim_idx]).ok()?);
+        self.buf.consume(delim_idx);
+
+        Some(res)
+    }
+
+    /// Returns `Some(Vec<u8>)` containing the next raw frame, split on the
+    /// byte-sequence delimiter set by `set_delim_bytes` (default `b"\n"`).
+    ///
+    /// Unlike `next()`, this never inspects the buffer as UTF-8, so it also
+    /// works on streams that are not valid text.
+    pub fn next_bytes(&mut self) -> Option<Vec<u8>> {
+        let delim = self.byte_delim.clone();
+
+        self.next_until(&delim)
+    }
+
+    /// Returns `Some(Vec<u8>)` containing everything up to (but excluding)
+    /// the next occurrence of `delim`, consuming the match itself along
+    /// with it. Returns `None` once the stream is exhausted with nothing
+    /// left to return.
+    ///
+    /// Like `next()`, we grow the lookahead buffer adaptively when `delim`
+    /// is not found, so a delimiter split across two reads is still
+    /// detected rather than missed at the buffer boundary.
+    pub fn next_until(&mut self, delim: &[u8]) -> Option<Vec<u8>> {
+        if delim.is_empty() {
+            return None;
+        }
+
+        let match_at;
+        let mut found_delim = true;
+        let mut stretch = MIN_STRETCH;
+
+        loop {
+            if let Some(idx) = find_subslice(self.buf.as_slice(), delim) {
+                match_at = idx;
                 break;
-            } else {
-                self.stream.grow(DEFAULT_BUF_SIZE);
             }
+
+            let before = self.buf.len();
+            self.buf.stretch(stretch);
+            match self.pull(stretch) {
+                Ok(0) if before == 0 => {
+                    // Truly exhausted: there is no frame left to return.
+                    return None;
+                }
+                Ok(0) => {
+                    // The stream is exhausted: whatever is left in the
+                    // buffer is the final frame.
+                    match_at = before;
+                    found_delim = false;
+                    break;
+                }
+                Ok(_n) => {}
+                Err(_e) => return None,
+            }
+
+            stretch = cmp::min(stretch * 2, MAX_STRETCH);
         }
-        self.stream.consume(delim_idx);
 
-        res.truncate(delim_idx);
-        res.shrink_to_fit();
-        
+        let res = self.buf.as_slice()[..match_at].to_vec();
+        let consumed = if found_delim {
+            match_at + delim.len()
+        } else {
+            match_at
+        };
+        self.buf.consume(consumed);
+
         Some(res)
     }
 
+    /// The counterpart to `next_bytes()` that writes the next frame into
+    /// `buf` instead of returning an owned `Vec`. Returns
+    /// `Some(n)` with the number of bytes written, or `None` if there was
+    /// no frame left.
+    ///
+    /// If the frame does not fit in `buf`, we return `None` and consume
+    /// nothing, so retrying with a larger `buf` sees the same frame again.
+    ///
+    /// Unlike `next()`, this never builds a `String` or calls into
+    /// `regex`. It still fills the scanner's own lookahead buffer, which
+    /// allocates (see the module docs).
+    pub fn next_into(&mut self, buf: &mut [u8]) -> Option<usize> {
+        let delim = self.byte_delim.clone();
+
+        self.next_until_into(&delim, buf)
+    }
+
+    /// The counterpart to `next_until()` that writes the next frame into
+    /// `buf` instead of returning an owned `Vec`. See
+    /// `next_into` for the exact contract.
+    pub fn next_until_into(&mut self, delim: &[u8], buf: &mut [u8]) -> Option<usize> {
+        if delim.is_empty() {
+            return None;
+        }
+
+        let match_at;
+        let mut found_delim = true;
+        let mut stretch = MIN_STRETCH;
+
+        loop {
+            if let Some(idx) = find_subslice(self.buf.as_slice(), delim) {
+                match_at = idx;
+                break;
+            }
+
+            let before = self.buf.len();
+            self.buf.stretch(stretch);
+            match self.pull(stretch) {
+                Ok(0) if before == 0 => {
+                    // Truly exhausted: there is no frame left to return.
+                    return None;
+                }
+                Ok(0) => {
+                    // The stream is exhausted: whatever is left in the
+                    // buffer is the final frame.
+                    match_at = before;
+                    found_delim = false;
+                    break;
+                }
+                Ok(_n) => {}
+                Err(_e) => return None,
+            }
+
+            stretch = cmp::min(stretch * 2, MAX_STRETCH);
+        }
+
+        if match_at > buf.len() {
+            // Leave the frame in the buffer untouched, so a retry with a
+            // larger `buf` observes the same frame rather than a partial
+            // or shifted one.
+            return None;
+        }
+
+        buf[..match_at].copy_from_slice(&self.buf.as_slice()[..match_at]);
+        let consumed = if found_delim {
+            match_at + delim.len()
+        } else {
+            match_at
+        };
+        self.buf.consume(consumed);
+
+        Some(match_at)
+    }
+
     /// Read up to (but excluding) the next `\n` character.
     /// If there are any leading `delim`s, they will be included in the
     /// returned string.
@@ -181,7 +544,7 @@ impl<R: Read + Sized> Scanner<R> {
     pub fn next_line(&mut self) -> Option<String> {
         let mut res = String::new();
 
-        if let Ok(_size) = self.stream.read_line(&mut res) {
+        if let Ok(_size) = self.read_line(&mut res) {
             if let Some(end) = res.pop() {
                 if end == '\n' {
                     Some(res)
@@ -291,50 +654,427 @@ impl<R: Read + Sized> Scanner<R> {
 
 /// Private helper functions for Scanner
 impl<R: Read + Sized> Scanner<R> {
+    /// Pulls more bytes from the underlying reader into our lookahead
+    /// buffer. `hint` caps how many bytes we attempt to read in total;
+    /// callers growing the lookahead via `ElasticQueue::stretch` pass the
+    /// same `stretch` value, so the adaptive doubling in `next()` and
+    /// friends actually controls how much I/O a miss costs, rather than
+    /// always pulling a fixed `DEFAULT_BUF_SIZE` regardless of how small
+    /// the token being searched for is.
+    ///
+    /// We read through a `MIN_STRETCH`-sized stack buffer in a loop rather
+    /// than a single read into a `hint`-sized one, since stable Rust has
+    /// no array sized by a runtime value: this keeps the stack footprint
+    /// fixed at `MIN_STRETCH` bytes regardless of how large `hint` grows,
+    /// which matters for `next_into`/`next_until_into` on a `no_std` target
+    /// with only a few KB of stack to spare. A short read (fewer bytes
+    /// than we asked for) ends the loop early, since it means the reader
+    /// has no more data ready right now.
+    ///
+    /// Returns the number of bytes read, with `Ok(0)` meaning the
+    /// underlying reader has reached EOF.
+    fn pull(&mut self, hint: usize) -> io::Result<usize> {
+        let want = cmp::min(hint, MAX_STRETCH);
+        let mut tmp = [0u8; MIN_STRETCH];
+        let mut total = 0;
+
+        while total < want {
+            let chunk = cmp::min(want - total, tmp.len());
+            let n = self.reader.read(&mut tmp[..chunk])?;
+            if n == 0 {
+                break;
+            }
+            self.buf.enqueue_slice(&tmp[..n]);
+            total += n;
+
+            if n < chunk {
+                break;
+            }
+        }
+
+        Ok(total)
+    }
+
     /// When we read `Scanner.next()`, we must first skip over any strings
     /// in the delimiting language before we begin reading the target text.
+    ///
+    /// A delimiter match that reaches exactly the end of what we have
+    /// buffered so far is not committed immediately: a greedy operator in
+    /// `delim` (e.g. `a[ab]*b`) could extend it further once more data
+    /// arrives, so we pull more and re-match before deciding it is final.
     fn leading_delims_offset(&mut self) -> usize {
         let mut res: usize = 0;
+        let mut stretch = MIN_STRETCH;
 
-        // We move `make_room` to the front because we are no longer consuming
-        // so multiple calls to it is just needless overhead
-        self.stream.make_room();
+        if self.buf.is_empty() {
+            let _ = self.pull(DEFAULT_BUF_SIZE);
+        }
 
         loop {
-            let length = {
-                if let Ok(buf) = self.stream.fill_buf() {
-                    // Note that since we are no longer consuming delims as
-                    // we find them, we must now slice into the buffer to
-                    // skip delims we've already encountered.
-                    if let Ok(text) = str::from_utf8(&buf[res..]) {
-                        if let Some(found) = self.delim.find(text) {
-                            if found.start() > 0 {
-                                return res;
-                            }
-
-                            found.end()
-                        } else {
-                            0
-                        }
-                    } else {
-                        0
+            let avail = self.buf.len();
+            let found = {
+                // Note that since we are no longer consuming delims as
+                // we find them, we must now slice into the buffer to
+                // skip delims we've already encountered.
+                let text = match str::from_utf8(&self.buf.as_slice()[res..]) {
+                    Ok(text) => text,
+                    Err(_e) => return res,
+                };
+                self.delim.find(text).map(|m| (m.start(), m.end()))
+            };
+
+            match found {
+                Some((start, _end)) if start > 0 => return res,
+                Some((_start, end)) if res + end < avail => {
+                    res += end;
+                    continue;
+                }
+                _ => {}
+            }
+
+            self.buf.stretch(stretch);
+            match self.pull(stretch) {
+                Ok(0) => {
+                    // EOF: a pending match at the boundary is now final.
+                    if let Some((0, end)) = found {
+                        res += end;
                     }
-                } else {
-                    0
+                    return res;
                 }
+                Ok(_n) => {}
+                Err(_e) => return res,
+            }
+            stretch = cmp::min(stretch * 2, MAX_STRETCH);
+        }
+    }
+
+    /// Runs the same leading-delimiter-skip + delimiter search as `next()`,
+    /// but never calls `self.buf.consume`, so the token (if any) is still
+    /// there for `next()` to return afterward. Backs the `has_next*`
+    /// family.
+    fn peek_next(&mut self) -> Option<String> {
+        let offset = self.leading_delims_offset();
+
+        let delim_idx;
+        let mut stretch = MIN_STRETCH;
+
+        loop {
+            let found = {
+                let text = str::from_utf8(&self.buf.as_slice()[offset..]).ok()?;
+                self.delim.find(text).map(|m| m.start())
             };
 
-            if length == 0 {
-                return res;
-            } else {
-                res += length;
+            if let Some(idx) = found {
+                delim_idx = offset + idx;
+                break;
+            }
+
+            let before = self.buf.len();
+            self.buf.stretch(stretch);
+            match self.pull(stretch) {
+                Ok(0) if before == offset => {
+                    // Truly exhausted: there is no token to peek at.
+                    return None;
+                }
+                Ok(0) => {
+                    // The stream is exhausted: whatever is left in the
+                    // buffer is the final token.
+                    delim_idx = before;
+                    break;
+                }
+                Ok(_n) => {}
+                Err(_e) => return None,
+            }
+
+            stretch = cmp::min(stretch * 2, MAX_STRETCH);
+        }
+
+        str::from_utf8(&self.buf.as_slice()[offset..delim_idx])
+            .ok()
+            .map(String::from)
+    }
+}
+
+/// Implements `BufRead` for `Scanner` directly over our own `ElasticQueue`,
+/// instead of delegating to an external buffered reader. `fill_buf` pulls
+/// from the underlying reader only when our buffer is empty, and `consume`
+/// is forwarded straight through.
+impl<R: Read + Sized> BufRead for Scanner<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.buf.is_empty() {
+            self.pull(DEFAULT_BUF_SIZE)?;
+        }
+
+        Ok(self.buf.as_slice())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.buf.consume(amt);
+    }
+}
+
+impl<R: Read + Sized> Read for Scanner<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let n = {
+            let avail = self.fill_buf()?;
+            let n = cmp::min(avail.len(), out.len());
+            out[..n].copy_from_slice(&avail[..n]);
+
+            n
+        };
+        self.consume(n);
+
+        Ok(n)
+    }
+}
+
+/// Reverse scanning: pulling tokens and lines from the *end* of a stream.
+///
+/// This requires `R: Seek` because it works by seeking backward by
+/// `BACK_CHUNK_SIZE` at a time, reading each chunk forward into its own
+/// buffer (kept separate from the forward `ElasticQueue`, since the two
+/// directions do not share a cursor), and searching that buffer from the
+/// right. If no delimiter boundary is found within the buffered region and
+/// we have not yet reached stream offset 0, we seek back another chunk and
+/// prepend it, so a token or delimiter that straddles a chunk boundary is
+/// still reconstructed whole.
+///
+/// NOTE: this reverse cursor seeks from the stream's true end and does not
+/// know what the forward `ElasticQueue` has already buffered. Mixing
+/// `next()`/`next_line()` with `next_back()`/`prev_line()` on the same
+/// `Scanner` can therefore re-examine bytes the forward side already
+/// buffered (though not yet returned); for correct results, scan a given
+/// stream in one direction only.
+impl<R: Read + Seek> Scanner<R> {
+    /// Returns `Some(String)` containing the token immediately before the
+    /// current reverse-scanning position, or `None` once stream offset 0
+    /// has been reached with nothing left to return.
+    ///
+    /// Mirrors `next()`, but walks backward from the end of the stream
+    /// instead of forward from the start, and is entirely independent of
+    /// any forward scanning done with `next()`/`next_line()`.
+    pub fn next_back(&mut self) -> Option<String> {
+        self.ensure_back_cursor().ok()?;
+
+        loop {
+            let buf_len = self.back.as_ref().unwrap().buf.len();
+
+            if let Some((start, end)) = self.rfind_delim(buf_len) {
+                if end == buf_len {
+                    // A trailing delimiter: drop it and keep looking for
+                    // the token it terminates.
+                    self.back.as_mut().unwrap().buf.truncate(start);
+                    continue;
+                }
+
+                let token = str::from_utf8(&self.back.as_ref().unwrap().buf[end..buf_len])
+                    .ok()
+                    .map(String::from);
+                self.back.as_mut().unwrap().buf.truncate(start);
+
+                return token;
+            }
+
+            if self.back.as_ref().unwrap().pos == 0 {
+                return self.take_remaining_back();
+            }
+
+            match self.pull_back() {
+                Ok(0) => return self.take_remaining_back(),
+                Ok(_n) => {}
+                Err(_e) => return None,
+            }
+        }
+    }
+
+    /// Returns `Some(String)` containing the line immediately before the
+    /// current reverse-scanning position, or `None` once stream offset 0
+    /// has been reached with nothing left to return.
+    ///
+    /// NOTE: like `next_back()` with its delimiter, the `\n` before the
+    /// returned line is consumed along with it rather than left in place,
+    /// mirroring `next_line()`'s treatment of its own trailing delimiter.
+    pub fn prev_line(&mut self) -> Option<String> {
+        let fresh = self.back.is_none();
+        self.ensure_back_cursor().ok()?;
+
+        if fresh {
+            // If the stream itself ends with `\n`, that terminates the
+            // final line rather than introducing an empty one after it
+            // (mirroring `next_line`'s own EOF behavior), so we drop it
+            // once, up front, before the first search.
+            if self.back.as_ref().unwrap().buf.is_empty()
+                && self.back.as_ref().unwrap().pos > 0
+            {
+                let _ = self.pull_back();
+            }
+
+            let cursor = self.back.as_mut().unwrap();
+            if cursor.buf.last() == Some(&b'\n') {
+                cursor.buf.pop();
+            }
+        }
+
+        loop {
+            let buf_len = self.back.as_ref().unwrap().buf.len();
+            let newline_idx = self.back.as_ref().unwrap().buf.iter().rposition(|&b| b == b'\n');
+
+            if let Some(idx) = newline_idx {
+                let line = str::from_utf8(&self.back.as_ref().unwrap().buf[idx + 1..buf_len])
+                    .ok()
+                    .map(String::from);
+                // Drop the newline itself along with the line it
+                // terminates, so it cannot be mistaken for a trailing
+                // newline on a later call.
+                self.back.as_mut().unwrap().buf.truncate(idx);
+
+                return line;
+            }
+
+            if self.back.as_ref().unwrap().pos == 0 {
+                return self.take_remaining_back();
+            }
+
+            match self.pull_back() {
+                Ok(0) => return self.take_remaining_back(),
+                Ok(_n) => {}
+                Err(_e) => return None,
             }
         }
     }
-/*
-    /// When we read `Scanner.next()` and `Scanner.has_next()`, we are doing
-    /// the same basic work, which has been exported here to avoid repetition.
+}
+
+/// Private helper functions for reverse scanning.
+impl<R: Read + Seek> Scanner<R> {
+    /// Initializes `self.back` to point at the current end of the stream,
+    /// the first time any reverse-scanning method is called.
+    fn ensure_back_cursor(&mut self) -> io::Result<()> {
+        if self.back.is_none() {
+            let len = self.reader.seek(SeekFrom::End(0))?;
+            self.back = Some(RevCursor {
+                pos: len,
+                buf: Vec::new(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Seeks back by one more `BACK_CHUNK_SIZE` (or to stream offset 0,
+    /// whichever comes first) and prepends what it reads to `self.back`'s
+    /// buffer. Returns the number of bytes read, with `Ok(0)` meaning
+    /// `self.back`'s cursor was already at offset 0.
+    fn pull_back(&mut self) -> io::Result<usize> {
+        let pos = self.back.as_ref().unwrap().pos;
+        if pos == 0 {
+            return Ok(0);
+        }
+
+        let chunk_len = cmp::min(BACK_CHUNK_SIZE as u64, pos) as usize;
+        let start = pos - chunk_len as u64;
+
+        self.reader.seek(SeekFrom::Start(start))?;
+        let mut chunk = vec![0u8; chunk_len];
+        self.reader.read_exact(&mut chunk)?;
+
+        let cursor = self.back.as_mut().unwrap();
+        chunk.extend_from_slice(&cursor.buf);
+        cursor.buf = chunk;
+        cursor.pos = start;
+
+        Ok(chunk_len)
+    }
+
+    /// Searches `self.back`'s buffered region `[.., end)` for the last
+    /// (rightmost) match of `delim`, returning its `(start, end)` byte
+    /// offsets.
     ///
-    /// We require that all leading delimiters have already been dealt with
-*/
+    /// If we have not yet reached stream offset 0, any leading bytes that
+    /// are the tail of an unread multi-byte character are excluded from
+    /// the search window: the character they belong to may not even
+    /// contain a delimiter, and in any case we cannot decode it until an
+    /// earlier `pull_back` brings in its leading byte.
+    fn rfind_delim(&self, end: usize) -> Option<(usize, usize)> {
+        let cursor = self.back.as_ref().unwrap();
+        let skip = if cursor.pos > 0 {
+            first_char_boundary(&cursor.buf[..end])
+        } else {
+            0
+        };
+
+        let text = str::from_utf8(&cursor.buf[skip..end]).ok()?;
+        self.delim
+            .find_iter(text)
+            .last()
+            .map(|m| (skip + m.start(), skip + m.end()))
+    }
+
+    /// Takes whatever remains in `self.back`'s buffer as the final token
+    /// or line, used once stream offset 0 has been reached (or the
+    /// underlying reader unexpectedly stops yielding more data).
+    fn take_remaining_back(&mut self) -> Option<String> {
+        let cursor = self.back.as_mut().unwrap();
+        if cursor.buf.is_empty() {
+            return None;
+        }
+
+        let res = str::from_utf8(&cursor.buf).ok().map(String::from);
+        cursor.buf.clear();
+
+        res
+    }
+}
+
+/// A double-ended iterator over a `Scanner`'s tokens, returned by
+/// `Scanner.tokens()`.
+///
+/// `.next()` drives the stream forward via `Scanner.next()`; `.next_back()`
+/// is only available when the underlying reader implements `Seek`, and
+/// drives it backward via `Scanner.next_back()`.
+pub struct Tokens<'a, R: Read + 'a> {
+    scanner: &'a mut Scanner<R>,
+}
+
+impl<'a, R: Read> Iterator for Tokens<'a, R> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.scanner.next()
+    }
+}
+
+impl<'a, R: Read + Seek> DoubleEndedIterator for Tokens<'a, R> {
+    fn next_back(&mut self) -> Option<String> {
+        self.scanner.next_back()
+    }
+}
+
+/// An iterator over a `Scanner`'s tokens parsed as integers, returned by
+/// `Scanner.ints()`.
+pub struct Ints<'a, R: Read + 'a, T> {
+    scanner: &'a mut Scanner<R>,
+    marker: PhantomData<T>,
+}
+
+impl<'a, R: Read, T: Integer> Iterator for Ints<'a, R, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.scanner.next_int::<T>()
+    }
+}
+
+/// An iterator over a `Scanner`'s tokens parsed as floats, returned by
+/// `Scanner.floats()`.
+pub struct Floats<'a, R: Read + 'a, T> {
+    scanner: &'a mut Scanner<R>,
+    marker: PhantomData<T>,
+}
+
+impl<'a, R: Read, T: Float> Iterator for Floats<'a, R, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.scanner.next_float::<T>()
+    }
 }
@@ -0,0 +1,109 @@
+// Copyright (c) Peter Sanders. All rights reserved.
+// Date: 2018-02-04
+
+//! A hand-rolled stand-in for the slice of `std::io` that `Scanner` needs
+//! under the `no_std` feature: `Read`, `BufRead`, `Seek`, `SeekFrom`,
+//! `Error` and `Result`.
+//!
+//! The `core_io` crate is not used for this: its `build.rs` only
+//! recognizes a hardcoded table of pre-2019 nightly commit hashes and
+//! panics ("Unknown compiler version") on any current toolchain. Rather
+//! than lean on an abandoned dependency, we implement just the handful of
+//! trait items this crate actually touches.
+
+extern crate alloc;
+use alloc::string::String;
+
+/// Opaque error type standing in for `std::io::Error`. `Scanner` never
+/// constructs or inspects one itself; it only needs to exist so a reader's
+/// failure can be propagated with `?`.
+#[derive(Debug)]
+pub struct Error;
+
+/// Mirrors `std::io::Result`.
+pub type Result<T> = core::result::Result<T, Error>;
+
+/// Mirrors `std::io::Read`.
+pub trait Read {
+    /// Pulls some bytes from this source into `buf`, returning the number
+    /// read, with `Ok(0)` meaning the source is exhausted.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Reads exactly `buf.len()` bytes, or fails if the source runs out
+    /// first. Mirrors `std::io::Read::read_exact`; used by `Scanner`'s
+    /// `Seek`-based reverse tokenization.
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        let mut buf = buf;
+
+        while !buf.is_empty() {
+            match self.read(buf)? {
+                0 => return Err(Error),
+                n => {
+                    let tmp = buf;
+                    buf = &mut tmp[n..];
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Mirrors `std::io::BufRead`.
+pub trait BufRead: Read {
+    /// Returns the contents of the internal buffer, filling it from the
+    /// underlying source first if it is empty.
+    fn fill_buf(&mut self) -> Result<&[u8]>;
+
+    /// Marks `amt` bytes of the buffer returned by `fill_buf` as consumed,
+    /// so they are not returned again.
+    fn consume(&mut self, amt: usize);
+
+    /// Reads bytes up to and including the next `\n` into `buf`, returning
+    /// the number of bytes read. Mirrors `std::io::BufRead::read_line`;
+    /// used by `Scanner::next_line`.
+    fn read_line(&mut self, buf: &mut String) -> Result<usize> {
+        let mut read = 0;
+
+        loop {
+            let (done, used) = {
+                let available = self.fill_buf()?;
+
+                match available.iter().position(|&b| b == b'\n') {
+                    Some(i) => {
+                        let text = core::str::from_utf8(&available[..=i]).map_err(|_| Error)?;
+                        buf.push_str(text);
+                        (true, i + 1)
+                    }
+                    None if available.is_empty() => (true, 0),
+                    None => {
+                        let text = core::str::from_utf8(available).map_err(|_| Error)?;
+                        buf.push_str(text);
+                        (false, available.len())
+                    }
+                }
+            };
+
+            self.consume(used);
+            read += used;
+
+            if done || used == 0 {
+                return Ok(read);
+            }
+        }
+    }
+}
+
+/// Mirrors `std::io::SeekFrom`.
+pub enum SeekFrom {
+    Start(u64),
+    End(i64),
+    Current(i64),
+}
+
+/// Mirrors `std::io::Seek`.
+pub trait Seek {
+    /// Seeks to an offset in bytes, relative to `pos`, returning the new
+    /// position from the start of the stream.
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+}
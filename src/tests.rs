@@ -7,6 +7,7 @@ extern crate buf_redux;
 use super::*;
 
 use buf_redux::BufReader;
+use std::io::Cursor;
 
 #[test]
 fn next_works_once_when_good_input() {
@@ -174,6 +175,14 @@ fn radix_between_2_36() {
     assert_eq!(test.get_radix(), 36);
 }
 
+#[test]
+fn buffer_capacity_reflects_constructor() {
+    let string: &[u8] = b"";
+    let test = Scanner::with_capacity(4, BufReader::new(string));
+
+    assert_eq!(test.buffer_capacity(), 4);
+}
+
 /// This test will fail if we cannot read past the length of the buffer.
 /// The buffer size is four characters, so it will read "hell". If we do
 /// not continue past the buffer, then it is interpreted as if we have
@@ -234,3 +243,260 @@ fn buffer_boundary_preserves_greed() {
     // If this test fails, we expect it to produce "bfoo" instead of "foo".
     assert_eq!(test.next(), Some(String::from("foo")));
 }
+
+#[test]
+fn next_back_reads_tokens_in_reverse() {
+    let mut test = Scanner::new(Cursor::new(b"hello world foo".to_vec()));
+
+    assert_eq!(test.next_back(), Some(String::from("foo")));
+    assert_eq!(test.next_back(), Some(String::from("world")));
+    assert_eq!(test.next_back(), Some(String::from("hello")));
+    assert_eq!(test.next_back(), None);
+}
+
+/// This test will fail if a delimiter trailing the last token is not
+/// stripped before we look for the token it terminates, since the regex
+/// match for it reaches the right edge of the buffer just like the first
+/// token's match does.
+#[test]
+fn next_back_skips_trailing_delim() {
+    let mut test = Scanner::new(Cursor::new(b"hello world   ".to_vec()));
+
+    assert_eq!(test.next_back(), Some(String::from("world")));
+    assert_eq!(test.next_back(), Some(String::from("hello")));
+}
+
+/// This test will fail if a token (or the chunk-straddling delimiter ahead
+/// of it) is not reconstructed across `BACK_CHUNK_SIZE` boundaries, since
+/// the string here is far larger than a single reverse-read chunk would
+/// need to be for a naive implementation to lose data at its edges.
+#[test]
+fn next_back_reconstructs_across_chunk_boundaries() {
+    let words: Vec<String> = (0..5000).map(|i| i.to_string()).collect();
+    let string = words.join(" ");
+    let mut test = Scanner::new(Cursor::new(string.into_bytes()));
+
+    let mut seen = Vec::new();
+    while let Some(token) = test.next_back() {
+        seen.push(token);
+    }
+    seen.reverse();
+
+    assert_eq!(seen, words);
+}
+
+#[test]
+fn next_back_handles_multibyte_utf8() {
+    let mut test = Scanner::new(Cursor::new("héllo wörld".as_bytes().to_vec()));
+
+    assert_eq!(test.next_back(), Some(String::from("wörld")));
+    assert_eq!(test.next_back(), Some(String::from("héllo")));
+    assert_eq!(test.next_back(), None);
+}
+
+#[test]
+fn prev_line_reads_lines_in_reverse() {
+    let mut test = Scanner::new(Cursor::new(b"line1\nline2\nline3".to_vec()));
+
+    assert_eq!(test.prev_line(), Some(String::from("line3")));
+    assert_eq!(test.prev_line(), Some(String::from("line2")));
+    assert_eq!(test.prev_line(), Some(String::from("line1")));
+    assert_eq!(test.prev_line(), None);
+}
+
+/// This test will fail if the stream's trailing `\n` is treated as
+/// introducing an empty final line instead of terminating the last real
+/// one, which would desynchronize `prev_line` from `next_line`'s own
+/// EOF behavior.
+#[test]
+fn prev_line_does_not_report_empty_line_after_trailing_newline() {
+    let mut test = Scanner::new(Cursor::new(b"line1\nline2\n".to_vec()));
+
+    assert_eq!(test.prev_line(), Some(String::from("line2")));
+    assert_eq!(test.prev_line(), Some(String::from("line1")));
+    assert_eq!(test.prev_line(), None);
+}
+
+#[test]
+fn tokens_supports_reverse_iteration() {
+    let mut test = Scanner::new(Cursor::new(b"a b c d e".to_vec()));
+
+    let reversed: Vec<String> = test.tokens().rev().collect();
+
+    assert_eq!(
+        reversed,
+        vec!["e", "d", "c", "b", "a"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn next_bytes_uses_default_newline_delim() {
+    let string: &[u8] = b"frame1\nframe2\nframe3";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    assert_eq!(test.next_bytes(), Some(b"frame1".to_vec()));
+    assert_eq!(test.next_bytes(), Some(b"frame2".to_vec()));
+    assert_eq!(test.next_bytes(), Some(b"frame3".to_vec()));
+    assert_eq!(test.next_bytes(), None);
+}
+
+#[test]
+fn next_bytes_honors_multi_byte_delim() {
+    let string: &[u8] = b"abc\r\ndef\r\nghi";
+    let mut test = Scanner::new(BufReader::new(string));
+    test.set_delim_bytes(b"\r\n");
+
+    assert_eq!(test.next_bytes(), Some(b"abc".to_vec()));
+    assert_eq!(test.next_bytes(), Some(b"def".to_vec()));
+    assert_eq!(test.next_bytes(), Some(b"ghi".to_vec()));
+}
+
+/// This test will fail if `next_bytes` ever validates the buffer as UTF-8,
+/// since these bytes are not a valid string.
+#[test]
+fn next_bytes_does_not_require_valid_utf8() {
+    let string: &[u8] = &[0xFF, 0xFE, 0x00, b'a', b'b'];
+    let mut test = Scanner::new(BufReader::new(string));
+    test.set_delim_bytes(&[0x00]);
+
+    assert_eq!(test.next_bytes(), Some(vec![0xFF, 0xFE]));
+    assert_eq!(test.next_bytes(), Some(vec![b'a', b'b']));
+}
+
+/// This test will fail if a multi-byte delimiter that straddles the
+/// boundary between two buffered reads is not reconstructed, the same
+/// class of bug `buffer_ends_within_end_delim` guards against for `next()`.
+#[test]
+fn next_bytes_reconstructs_delim_across_buffer_boundary() {
+    // The first read yields only "abc\r", so "\r\n" spans two reads.
+    let string = (&b"abc\r"[..]).chain(&b"\ndef"[..]);
+    let mut test = Scanner::with_capacity(4, string);
+    test.set_delim_bytes(b"\r\n");
+
+    assert_eq!(test.next_bytes(), Some(b"abc".to_vec()));
+    assert_eq!(test.next_bytes(), Some(b"def".to_vec()));
+}
+
+#[test]
+fn next_until_searches_for_an_explicit_delim() {
+    let string: &[u8] = b"key=value;rest";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    assert_eq!(test.next_until(b"="), Some(b"key".to_vec()));
+    assert_eq!(test.next_until(b";"), Some(b"value".to_vec()));
+}
+
+#[test]
+fn next_into_writes_into_caller_buffer() {
+    let string: &[u8] = b"frame1\nframe2";
+    let mut test = Scanner::new(BufReader::new(string));
+    let mut buf = [0u8; 16];
+
+    assert_eq!(test.next_into(&mut buf), Some(6));
+    assert_eq!(&buf[..6], b"frame1");
+    assert_eq!(test.next_into(&mut buf), Some(6));
+    assert_eq!(&buf[..6], b"frame2");
+    assert_eq!(test.next_into(&mut buf), None);
+}
+
+/// This test will fail if `next_into` consumes a frame that did not fit in
+/// `buf`, which would make it impossible for the caller to retry with a
+/// larger buffer.
+#[test]
+fn next_into_does_not_consume_when_buffer_is_too_small() {
+    let string: &[u8] = b"toolong\nrest";
+    let mut test = Scanner::new(BufReader::new(string));
+    let mut small = [0u8; 4];
+    let mut big = [0u8; 16];
+
+    assert_eq!(test.next_into(&mut small), None);
+    assert_eq!(test.next_into(&mut big), Some(7));
+    assert_eq!(&big[..7], b"toolong");
+}
+
+#[test]
+fn tokens_iterator_collects_all() {
+    let string: &[u8] = b"one two three";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    let tokens: Vec<String> = test.tokens().collect();
+    assert_eq!(tokens, vec!["one", "two", "three"]);
+}
+
+#[test]
+fn ints_iterator_sums() {
+    let string: &[u8] = b"1 2 3 4 5";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    let sum: u64 = test.ints::<u64>().sum();
+    assert_eq!(sum, 15);
+}
+
+/// This test will fail if the iterator skips over a non-numeric token to
+/// find the next valid one, rather than stopping, mirroring `next_int`'s
+/// own "even if this fails, we still consume `next`" behavior.
+#[test]
+fn ints_iterator_stops_at_first_non_numeric_token() {
+    let string: &[u8] = b"1 2 notanumber 4";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    let ints: Vec<i32> = test.ints::<i32>().collect();
+    assert_eq!(ints, vec![1, 2]);
+}
+
+#[test]
+fn floats_iterator_collects() {
+    let string: &[u8] = b"1.5 2.5 3.5";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    let floats: Vec<f64> = test.floats::<f64>().collect();
+    assert_eq!(floats, vec![1.5, 2.5, 3.5]);
+}
+
+/// This test will fail if `has_next` consumes the token it found, since
+/// `next()` afterward would then skip straight past it.
+#[test]
+fn has_next_does_not_consume_the_token() {
+    let string: &[u8] = b"hello world";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    assert!(test.has_next());
+    assert!(test.has_next());
+    assert_eq!(test.next(), Some(String::from("hello")));
+    assert_eq!(test.next(), Some(String::from("world")));
+    assert!(!test.has_next());
+}
+
+#[test]
+fn has_next_int_checks_parseability_without_consuming() {
+    let string: &[u8] = b"notanumber 42";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    assert!(!test.has_next_int::<i32>());
+    // Still there for `next()` to read: `has_next_int` did not consume it.
+    assert_eq!(test.next(), Some(String::from("notanumber")));
+    assert!(test.has_next_int::<i32>());
+    assert_eq!(test.next_int::<i32>(), Some(42));
+}
+
+#[test]
+fn has_next_line_is_false_once_exhausted() {
+    let string: &[u8] = b"only line";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    assert!(test.has_next_line());
+    test.next_line();
+    assert!(!test.has_next_line());
+}
+
+#[test]
+fn has_next_on_empty_stream() {
+    let string: &[u8] = b"";
+    let mut test = Scanner::new(BufReader::new(string));
+
+    assert!(!test.has_next());
+    assert!(!test.has_next_line());
+}